@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::store::{Server, ServerList};
+
+const DIRECTORY_CACHE_FILE: &str = "directory_cache.json";
+const DEFAULT_DIRECTORY_INDEX_URL: &str = "https://realmlister.example.com/directory.json";
+
+pub fn default_directory_index_url() -> String {
+    DEFAULT_DIRECTORY_INDEX_URL.to_string()
+}
+
+/// One entry in the remote server directory index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryEntry {
+    pub name: String,
+    pub realmlist_host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub expansion: String,
+    #[serde(default)]
+    pub player_count: u32,
+    #[serde(default)]
+    pub description: String,
+}
+
+fn default_port() -> u16 {
+    3724
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ServerDirectory {
+    pub entries: Vec<DirectoryEntry>,
+}
+
+fn directory_cache_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(DIRECTORY_CACHE_FILE)
+}
+
+fn load_cached_directory(app_data_dir: &Path) -> Result<ServerDirectory, String> {
+    let path = directory_cache_path(app_data_dir);
+    if !path.exists() {
+        return Ok(ServerDirectory::default());
+    }
+    let data = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map_err(|e| e.to_string())
+}
+
+fn save_cached_directory(app_data_dir: &Path, directory: &ServerDirectory) -> Result<(), String> {
+    std::fs::create_dir_all(app_data_dir).map_err(|e| e.to_string())?;
+    let path = directory_cache_path(app_data_dir);
+    let data = serde_json::to_string_pretty(directory).map_err(|e| e.to_string())?;
+    std::fs::write(path, data).map_err(|e| e.to_string())
+}
+
+/// Fetches the remote server directory index, falling back to the last
+/// successful fetch cached on disk if the request fails (e.g. offline).
+pub async fn fetch_directory(app_data_dir: &Path, index_url: &str) -> Result<ServerDirectory, String> {
+    let index_url = index_url.trim().to_string();
+    let dir = app_data_dir.to_path_buf();
+    match fetch_remote_directory(&index_url).await {
+        Ok(directory) => {
+            save_cached_directory(&dir, &directory)?;
+            Ok(directory)
+        }
+        Err(e) => {
+            let cached = load_cached_directory(&dir)?;
+            if cached.entries.is_empty() {
+                Err(e)
+            } else {
+                Ok(cached)
+            }
+        }
+    }
+}
+
+async fn fetch_remote_directory(index_url: &str) -> Result<ServerDirectory, String> {
+    if index_url.is_empty() {
+        return Err("No directory index URL configured".to_string());
+    }
+    let body = reqwest::get(index_url)
+        .await
+        .map_err(|e| format!("Could not reach {}: {}", index_url, e))?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+    let entries: Vec<DirectoryEntry> = serde_json::from_str(&body).map_err(|e| e.to_string())?;
+    Ok(ServerDirectory { entries })
+}
+
+/// Imports the given directory entries into `list`, reusing the same
+/// id-assignment rule as `add_server` and skipping entries whose host and
+/// port are already present.
+pub fn merge_import_entries(list: &mut ServerList, entries: Vec<DirectoryEntry>) {
+    for entry in entries {
+        let already_present = list
+            .servers
+            .iter()
+            .any(|s| s.realmlist_host == entry.realmlist_host && s.port == entry.port);
+        if already_present {
+            continue;
+        }
+        list.servers.push(Server {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: entry.name,
+            realmlist_host: entry.realmlist_host,
+            port: entry.port,
+            wow_path: None,
+            wow_exe: "Wow.exe".to_string(),
+            account_name: None,
+            launch_args: Vec::new(),
+            env: Vec::new(),
+            pre_launch: None,
+            post_launch: None,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, host: &str, port: u16) -> DirectoryEntry {
+        DirectoryEntry {
+            name: name.to_string(),
+            realmlist_host: host.to_string(),
+            port,
+            expansion: String::new(),
+            player_count: 0,
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn imports_new_entries() {
+        let mut list = ServerList::default();
+        merge_import_entries(&mut list, vec![entry("Test Realm", "logon.example.com", 3724)]);
+
+        assert_eq!(list.servers.len(), 1);
+        assert_eq!(list.servers[0].name, "Test Realm");
+        assert_eq!(list.servers[0].realmlist_host, "logon.example.com");
+        assert!(!list.servers[0].id.is_empty());
+    }
+
+    #[test]
+    fn skips_hosts_already_present() {
+        let mut list = ServerList::default();
+        merge_import_entries(&mut list, vec![entry("Existing", "logon.example.com", 3724)]);
+        merge_import_entries(&mut list, vec![entry("Duplicate", "logon.example.com", 3724)]);
+
+        assert_eq!(list.servers.len(), 1);
+        assert_eq!(list.servers[0].name, "Existing");
+    }
+
+    #[test]
+    fn same_host_different_port_is_not_a_duplicate() {
+        let mut list = ServerList::default();
+        merge_import_entries(&mut list, vec![entry("Realm A", "logon.example.com", 3724)]);
+        merge_import_entries(&mut list, vec![entry("Realm B", "logon.example.com", 3725)]);
+
+        assert_eq!(list.servers.len(), 2);
+    }
+}
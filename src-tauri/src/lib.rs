@@ -1,6 +1,10 @@
+mod directory;
+mod monitor;
+mod process;
 mod realmlist;
 mod store;
 
+use directory::ServerDirectory;
 use realmlist::write_realmlist;
 use store::{
     load_servers, load_settings, save_servers, save_settings, AppSettings, Server, ServerList,
@@ -69,6 +73,10 @@ fn update_server(
             server.wow_exe
         };
         s.account_name = server.account_name;
+        s.launch_args = server.launch_args;
+        s.env = server.env;
+        s.pre_launch = server.pre_launch;
+        s.post_launch = server.post_launch;
     }
     save_servers(&dir, &list)?;
     Ok(list)
@@ -97,13 +105,53 @@ fn save_settings_cmd(app: tauri::AppHandle, settings: AppSettings) -> Result<(),
 
 #[tauri::command]
 fn write_realmlist_cmd(
+    app: tauri::AppHandle,
     wow_path: String,
     host: String,
     locale: Option<String>,
     account_name: Option<String>,
 ) -> Result<(), String> {
+    let dir = app_data_dir(&app)?;
     let locale = locale.unwrap_or_else(|| "enUS".to_string());
-    write_realmlist(&wow_path, &host, &locale, account_name.as_deref())
+    write_realmlist(&dir, &wow_path, &host, &locale, account_name.as_deref())
+}
+
+#[tauri::command]
+fn list_realmlist_backups(host: String, app: tauri::AppHandle) -> Result<Vec<realmlist::RealmlistBackup>, String> {
+    let dir = app_data_dir(&app)?;
+    realmlist::list_backups(&dir, &host)
+}
+
+#[tauri::command]
+fn restore_realmlist_backup(
+    app: tauri::AppHandle,
+    wow_path: String,
+    host: String,
+    locale: Option<String>,
+    snapshot_id: String,
+) -> Result<(), String> {
+    let dir = app_data_dir(&app)?;
+    let locale = locale.unwrap_or_else(|| "enUS".to_string());
+    realmlist::restore_backup(&dir, &wow_path, &host, &locale, &snapshot_id)
+}
+
+#[tauri::command]
+async fn fetch_server_directory(app: tauri::AppHandle) -> Result<ServerDirectory, String> {
+    let dir = app_data_dir(&app)?;
+    let settings = load_settings(&dir)?;
+    directory::fetch_directory(&dir, &settings.directory_index_url).await
+}
+
+#[tauri::command]
+fn import_directory_entries(
+    app: tauri::AppHandle,
+    entries: Vec<directory::DirectoryEntry>,
+) -> Result<ServerList, String> {
+    let dir = app_data_dir(&app)?;
+    let mut list = load_servers(&dir)?;
+    directory::merge_import_entries(&mut list, entries);
+    save_servers(&dir, &list)?;
+    Ok(list)
 }
 
 #[derive(serde::Deserialize)]
@@ -112,8 +160,23 @@ struct PlayWowArgs {
     server_id: String,
 }
 
+/// Outcome of a `play_wow` attempt, distinguishing a fresh launch from an
+/// already-running instance for this server so the UI doesn't stomp
+/// `Config.wtf` and relaunch while a session is live.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub(crate) enum PlayWowOutcome {
+    /// The client launched. `post_launch_warning` is set if the client
+    /// launched successfully but its `post_launch` hook failed — that
+    /// failure is surfaced as a warning rather than a launch failure, since
+    /// the game is already running by the time the hook runs.
+    Launched { post_launch_warning: Option<String> },
+    AlreadyRunning { pid: u32 },
+    Failed { reason: String },
+}
+
 #[tauri::command]
-fn play_wow(app: tauri::AppHandle, args: PlayWowArgs) -> Result<(), String> {
+fn play_wow(app: tauri::AppHandle, args: PlayWowArgs) -> Result<PlayWowOutcome, String> {
     let dir = app_data_dir(&app)?;
     let list = load_servers(&dir)?;
     let server = list
@@ -142,59 +205,157 @@ fn play_wow(app: tauri::AppHandle, args: PlayWowArgs) -> Result<(), String> {
             wow_exe.display()
         ));
     }
+
+    if let Some(running) = process::find_running(&wow_exe).into_iter().next() {
+        return Ok(PlayWowOutcome::AlreadyRunning { pid: running.pid });
+    }
+
     let locale = if settings.realmlist_locale.is_empty() {
         "enUS".to_string()
     } else {
         settings.realmlist_locale.clone()
     };
-    write_realmlist(wow_path, &server.realmlist_host, &locale, server.account_name.as_deref())?;
-    std::process::Command::new(&wow_exe)
-        .current_dir(wow_path_buf.parent().unwrap_or(Path::new(".")))
-        .spawn()
-        .map_err(|e| e.to_string())?;
+    write_realmlist(&dir, wow_path, &server.realmlist_host, &locale, server.account_name.as_deref())?;
+
+    if let Some(pre_launch) = non_empty(server.pre_launch.as_deref()) {
+        run_hook(pre_launch)?;
+    }
+
+    let mut command = std::process::Command::new(&wow_exe);
+    command.current_dir(wow_path_buf.parent().unwrap_or(Path::new(".")));
+    command.args(server.launch_args.iter().map(|a| a.trim()).filter(|a| !a.is_empty()));
+    command.envs(
+        server
+            .env
+            .iter()
+            .filter(|(k, _)| !k.trim().is_empty())
+            .map(|(k, v)| (k.as_str(), v.as_str())),
+    );
+
+    if let Err(e) = command.spawn() {
+        return Ok(PlayWowOutcome::Failed { reason: e.to_string() });
+    }
+
+    // The client is already running at this point, so a failing post-launch
+    // hook is a warning, not a launch failure.
+    let post_launch_warning =
+        non_empty(server.post_launch.as_deref()).and_then(|post_launch| run_hook(post_launch).err());
+
+    Ok(PlayWowOutcome::Launched { post_launch_warning })
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(|v| v.trim()).filter(|v| !v.is_empty())
+}
+
+/// Runs a hook shell command to completion, surfacing a non-zero exit as an error.
+fn run_hook(command: &str) -> Result<(), String> {
+    let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+    let status = std::process::Command::new(shell)
+        .arg(flag)
+        .arg(command)
+        .status()
+        .map_err(|e| format!("Hook command failed to start: {}", e))?;
+    if !status.success() {
+        return Err(format!("Hook command exited with {}", status));
+    }
     Ok(())
 }
 
+/// Reports every running WoW client matching any configured server's
+/// executable name, correlated back to `Server.id` by matching the
+/// process's executable path against each server's `wow_path`.
+#[tauri::command]
+fn list_running_wow(app: tauri::AppHandle) -> Result<Vec<process::RunningWowProcess>, String> {
+    let dir = app_data_dir(&app)?;
+    let list = load_servers(&dir)?;
+
+    let mut seen_exe_names = std::collections::HashSet::new();
+    let mut running = Vec::new();
+    for server in &list.servers {
+        let exe_name = server.wow_exe.trim();
+        let exe_name = if exe_name.is_empty() { "Wow.exe" } else { exe_name };
+        if seen_exe_names.insert(exe_name.to_lowercase()) {
+            running.extend(process::find_all(exe_name));
+        }
+    }
+
+    for proc in running.iter_mut() {
+        let Some(exe_path) = proc.exe_path.as_deref() else {
+            continue;
+        };
+        let exe_path = std::path::PathBuf::from(exe_path);
+        if let Some(server) = list.servers.iter().find(|s| {
+            s.wow_path
+                .as_deref()
+                .map(|p| exe_path.starts_with(p))
+                .unwrap_or(false)
+        }) {
+            proc.server_id = Some(server.id.clone());
+        }
+    }
+
+    Ok(running)
+}
+
 #[tauri::command]
 async fn check_realm_status(host: String, port: Option<u16>) -> Result<RealmStatus, String> {
     let port = port.unwrap_or(3724);
     // Run blocking TCP work off the main thread so the UI stays responsive
-    tauri::async_runtime::spawn_blocking(move || {
-        let host = host.trim();
-        let start = std::time::Instant::now();
-        let addrs: Vec<_> = (host, port)
-            .to_socket_addrs()
-            .map_err(|e| format!("Could not resolve {}:{}: {}", host, port, e))?
-            .collect();
-        for addr in addrs {
-            if TcpStream::connect_timeout(&addr, Duration::from_secs(3)).is_ok() {
-                return Ok(RealmStatus {
-                    online: true,
-                    latency_ms: start.elapsed().as_millis() as u64,
-                });
-            }
+    tauri::async_runtime::spawn_blocking(move || probe_status(&host, port))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Resolves `host:port` and attempts a TCP connect, reporting whether the
+/// realm is reachable and how long the connect took. Shared by the
+/// `check_realm_status` command and the background status monitor.
+pub(crate) fn probe_status(host: &str, port: u16) -> Result<RealmStatus, String> {
+    let host = host.trim();
+    let start = std::time::Instant::now();
+    let addrs: Vec<_> = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| format!("Could not resolve {}:{}: {}", host, port, e))?
+        .collect();
+    for addr in addrs {
+        if TcpStream::connect_timeout(&addr, Duration::from_secs(3)).is_ok() {
+            return Ok(RealmStatus {
+                online: true,
+                latency_ms: start.elapsed().as_millis() as u64,
+            });
         }
-        Ok(RealmStatus {
-            online: false,
-            latency_ms: 0,
-        })
+    }
+    Ok(RealmStatus {
+        online: false,
+        latency_ms: 0,
     })
-    .await
-    .map_err(|e| e.to_string())?
 }
 
-#[derive(serde::Serialize)]
-struct RealmStatus {
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct RealmStatus {
     online: bool,
     latency_ms: u64,
 }
 
+impl RealmStatus {
+    pub(crate) fn offline() -> Self {
+        RealmStatus {
+            online: false,
+            latency_ms: 0,
+        }
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
+        .setup(|app| {
+            monitor::spawn(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             get_servers,
             save_servers_cmd,
@@ -204,8 +365,13 @@ pub fn run() {
             get_settings,
             save_settings_cmd,
             write_realmlist_cmd,
+            list_realmlist_backups,
+            restore_realmlist_backup,
             play_wow,
+            list_running_wow,
             check_realm_status,
+            fetch_server_directory,
+            import_directory_entries,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter};
+
+use crate::store::load_settings;
+use crate::{app_data_dir, load_servers, probe_status, RealmStatus};
+
+/// Latency swing (in ms) between successive polls that counts as a change
+/// worth notifying about, even if the realm stayed online the whole time.
+const LATENCY_CHANGE_THRESHOLD_MS: u64 = 500;
+const MAX_BACKOFF_SECS: u64 = 300;
+
+#[derive(Clone, serde::Serialize)]
+struct RealmStatusChangedPayload {
+    server_id: String,
+    online: bool,
+    latency_ms: u64,
+}
+
+/// Starts the background loop that keeps `last_status` up to date for every
+/// configured server and emits `realm-status-changed` the first time a server
+/// is polled, and after that only when a realm flips online/offline or its
+/// latency crosses `LATENCY_CHANGE_THRESHOLD_MS`.
+/// Runs as a single sequential loop, so there is never more than one poll of
+/// the list in flight at a time.
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_status: HashMap<String, RealmStatus> = HashMap::new();
+        loop {
+            let sleep_secs = poll_once(&app, &mut last_status).await;
+            tokio::time::sleep(Duration::from_secs(sleep_secs)).await;
+        }
+    });
+}
+
+async fn poll_once(app: &AppHandle, last_status: &mut HashMap<String, RealmStatus>) -> u64 {
+    let dir = match app_data_dir(app) {
+        Ok(dir) => dir,
+        Err(_) => return default_interval(),
+    };
+    let interval = load_settings(&dir)
+        .map(|s| if s.poll_interval_secs == 0 { default_interval() } else { s.poll_interval_secs })
+        .unwrap_or_else(|_| default_interval());
+    let servers = match load_servers(&dir) {
+        Ok(list) => list.servers,
+        Err(_) => return interval,
+    };
+    if servers.is_empty() {
+        return interval;
+    }
+
+    let mut any_online = false;
+    for server in servers {
+        let host = server.realmlist_host.clone();
+        let port = server.port;
+        let status = tauri::async_runtime::spawn_blocking(move || probe_status(&host, port))
+            .await
+            .unwrap_or(Ok(RealmStatus::offline()))
+            .unwrap_or(RealmStatus::offline());
+
+        any_online = any_online || status.online;
+
+        if has_changed(last_status.get(&server.id), &status) {
+            let _ = app.emit(
+                "realm-status-changed",
+                RealmStatusChangedPayload {
+                    server_id: server.id.clone(),
+                    online: status.online,
+                    latency_ms: status.latency_ms,
+                },
+            );
+        }
+        last_status.insert(server.id, status);
+    }
+
+    if any_online {
+        interval
+    } else {
+        (interval * 2).min(MAX_BACKOFF_SECS)
+    }
+}
+
+fn has_changed(previous: Option<&RealmStatus>, current: &RealmStatus) -> bool {
+    match previous {
+        None => true,
+        Some(prev) => {
+            prev.online != current.online
+                || (current.online
+                    && prev.latency_ms.abs_diff(current.latency_ms) >= LATENCY_CHANGE_THRESHOLD_MS)
+        }
+    }
+}
+
+fn default_interval() -> u64 {
+    30
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(online: bool, latency_ms: u64) -> RealmStatus {
+        RealmStatus { online, latency_ms }
+    }
+
+    #[test]
+    fn first_sighting_is_always_a_change() {
+        assert!(has_changed(None, &status(true, 20)));
+        assert!(has_changed(None, &status(false, 0)));
+    }
+
+    #[test]
+    fn online_offline_flip_is_a_change() {
+        assert!(has_changed(Some(&status(true, 20)), &status(false, 0)));
+        assert!(has_changed(Some(&status(false, 0)), &status(true, 20)));
+    }
+
+    #[test]
+    fn small_latency_swing_while_online_is_not_a_change() {
+        assert!(!has_changed(Some(&status(true, 20)), &status(true, 40)));
+    }
+
+    #[test]
+    fn latency_swing_crossing_threshold_while_online_is_a_change() {
+        assert!(has_changed(
+            Some(&status(true, 20)),
+            &status(true, 20 + LATENCY_CHANGE_THRESHOLD_MS)
+        ));
+    }
+
+    #[test]
+    fn latency_swing_while_offline_is_not_a_change() {
+        assert!(!has_changed(Some(&status(false, 0)), &status(false, 900)));
+    }
+}
@@ -0,0 +1,60 @@
+use std::path::Path;
+
+use sysinfo::{ProcessesToUpdate, System};
+
+/// A running process that matches a WoW client executable name.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RunningWowProcess {
+    pub pid: u32,
+    pub exe_path: Option<String>,
+    /// `Server.id` this process was correlated back to, if any.
+    pub server_id: Option<String>,
+}
+
+fn matching_processes(system: &System, exe_name: &str) -> Vec<RunningWowProcess> {
+    let exe_name_lower = exe_name.to_lowercase();
+    system
+        .processes()
+        .values()
+        .filter(|process| process.name().to_string_lossy().to_lowercase() == exe_name_lower)
+        .map(|process| RunningWowProcess {
+            pid: process.pid().as_u32(),
+            exe_path: process.exe().map(|p| p.display().to_string()),
+            server_id: None,
+        })
+        .collect()
+}
+
+/// Finds running instances whose executable path is `wow_exe` — the exact
+/// file RealmLister would launch — used to guard against double-launching.
+/// Matches on the resolved exe path rather than the process's working
+/// directory, since the client is spawned with its *parent* directory as
+/// the cwd, not `wow_path` itself.
+pub fn find_running(wow_exe: &Path) -> Vec<RunningWowProcess> {
+    let mut system = System::new_all();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+
+    system
+        .processes()
+        .values()
+        .filter(|process| {
+            process
+                .exe()
+                .map(|exe| exe == wow_exe)
+                .unwrap_or(false)
+        })
+        .map(|process| RunningWowProcess {
+            pid: process.pid().as_u32(),
+            exe_path: process.exe().map(|p| p.display().to_string()),
+            server_id: None,
+        })
+        .collect()
+}
+
+/// Finds every running instance of `exe_name`, regardless of working
+/// directory, for `list_running_wow` to report across all servers.
+pub fn find_all(exe_name: &str) -> Vec<RunningWowProcess> {
+    let mut system = System::new_all();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+    matching_processes(&system, exe_name)
+}
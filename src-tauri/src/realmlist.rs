@@ -1,12 +1,27 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 const REALMLIST_LINE_PREFIX: &str = "set realmlist ";
+const BACKUPS_DIR: &str = "realmlist_backups";
+
+const ROOT_REALMLIST_SNAPSHOT_NAME: &str = "realmlist.wtf";
+const CONFIG_WTF_SNAPSHOT_NAME: &str = "config.wtf";
+
+fn data_realmlist_snapshot_name(locale: &str) -> String {
+    format!("data_{}_realmlist.wtf", locale)
+}
 
 /// Writes realmlist to all locations WoW clients may read from:
 /// - `wow_path/realmlist.wtf` (root, used by some older clients)
 /// - `wow_path/Data/{locale}/realmlist.wtf`
 /// - `wow_path/WTF/Config.wtf` (adds or updates the realmlist line)
+///
+/// Because `Config.wtf` holds many unrelated client settings, every file
+/// touched here is snapshotted into a timestamped backup directory under
+/// `app_data_dir` first, and writes are atomic (temp file + rename) so a
+/// crash mid-write can't corrupt `Config.wtf`.
 pub fn write_realmlist(
+    app_data_dir: &Path,
     wow_path: &str,
     host: &str,
     locale: &str,
@@ -16,28 +31,38 @@ pub fn write_realmlist(
     let host = host.trim();
     let content = format!("{}{}", REALMLIST_LINE_PREFIX, host);
 
+    // Key the snapshot by the realm the file currently points at, not the
+    // one we're about to switch to — otherwise switching from server A to
+    // server B on the same `wow_path` files A's backup under B's directory.
+    let backup_key = existing_realmlist_host(base).unwrap_or_else(|| host.to_string());
+    let snapshot_dir = snapshot_dir_for(app_data_dir, &backup_key);
+
     // 1. Root realmlist.wtf (some older/custom clients read from here)
     let root_realmlist = base.join("realmlist.wtf");
-    std::fs::write(&root_realmlist, &content).map_err(|e| e.to_string())?;
+    snapshot_if_exists(&root_realmlist, &snapshot_dir, ROOT_REALMLIST_SNAPSHOT_NAME)?;
+    atomic_write(&root_realmlist, &content)?;
 
     // 2. Data/{locale}/realmlist.wtf (primary)
     let data_dir = base.join("Data").join(locale);
-    std::fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
     let realmlist_wtf = data_dir.join("realmlist.wtf");
-    std::fs::write(&realmlist_wtf, &content).map_err(|e| e.to_string())?;
+    snapshot_if_exists(&realmlist_wtf, &snapshot_dir, &data_realmlist_snapshot_name(locale))?;
+    atomic_write(&realmlist_wtf, &content)?;
 
     // 3. WTF/Config.wtf (realmlist + accountName)
     let wtf_dir = base.join("WTF");
     let config_wtf = wtf_dir.join("Config.wtf");
+    snapshot_if_exists(&config_wtf, &snapshot_dir, CONFIG_WTF_SNAPSHOT_NAME)?;
     write_realmlist_into_config(&config_wtf, host, account_name)?;
 
     Ok(())
 }
 
 /// Updates or adds the realmlist and accountName lines in WTF/Config.wtf.
-/// Preserves all other lines.
+/// Preserves all other non-blank lines; blank lines are dropped. Written
+/// atomically; the caller is responsible
+/// for snapshotting the prior contents before calling this.
 fn write_realmlist_into_config(
-    config_path: &std::path::Path,
+    config_path: &Path,
     host: &str,
     account_name: Option<&str>,
 ) -> Result<(), String> {
@@ -80,8 +105,277 @@ fn write_realmlist_into_config(
 
     lines.retain(|l| !l.is_empty());
 
-    std::fs::create_dir_all(config_path.parent().unwrap_or(Path::new(".")))
-        .map_err(|e| e.to_string())?;
     let content = lines.join("\r\n");
-    std::fs::write(config_path, content).map_err(|e| e.to_string())
+    atomic_write(config_path, &content)
+}
+
+/// One `realmlist.wtf`/`Config.wtf` backup taken before a write, identified
+/// by the moment it was taken.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RealmlistBackup {
+    pub snapshot_id: String,
+}
+
+/// Lists available backup snapshots for `host`, most recent first.
+pub fn list_backups(app_data_dir: &Path, host: &str) -> Result<Vec<RealmlistBackup>, String> {
+    let dir = backup_root(app_data_dir, host);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut snapshot_ids: Vec<String> = std::fs::read_dir(&dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    snapshot_ids.sort();
+    snapshot_ids.reverse();
+    Ok(snapshot_ids
+        .into_iter()
+        .map(|snapshot_id| RealmlistBackup { snapshot_id })
+        .collect())
+}
+
+/// Reinstates a previously taken snapshot, restoring whichever of the three
+/// realmlist/Config.wtf files were captured in it. Files that didn't exist
+/// at backup time (and so weren't captured) are left untouched.
+pub fn restore_backup(
+    app_data_dir: &Path,
+    wow_path: &str,
+    host: &str,
+    locale: &str,
+    snapshot_id: &str,
+) -> Result<(), String> {
+    if !is_safe_snapshot_id(snapshot_id) {
+        return Err(format!("Invalid backup snapshot id: {}", snapshot_id));
+    }
+    let snapshot_dir = backup_root(app_data_dir, host).join(snapshot_id);
+    if !snapshot_dir.exists() {
+        return Err(format!("No such backup snapshot: {}", snapshot_id));
+    }
+    let base = Path::new(wow_path);
+
+    restore_one(
+        &snapshot_dir.join(ROOT_REALMLIST_SNAPSHOT_NAME),
+        &base.join("realmlist.wtf"),
+    )?;
+    restore_one(
+        &snapshot_dir.join(data_realmlist_snapshot_name(locale)),
+        &base.join("Data").join(locale).join("realmlist.wtf"),
+    )?;
+    restore_one(
+        &snapshot_dir.join(CONFIG_WTF_SNAPSHOT_NAME),
+        &base.join("WTF").join("Config.wtf"),
+    )?;
+    Ok(())
+}
+
+fn restore_one(snapshot_file: &Path, destination: &Path) -> Result<(), String> {
+    if !snapshot_file.exists() {
+        return Ok(());
+    }
+    let content = std::fs::read_to_string(snapshot_file).map_err(|e| e.to_string())?;
+    atomic_write(destination, &content)
+}
+
+/// Snapshot ids are always generated by `snapshot_dir_for` as a millisecond
+/// timestamp, so requiring digits-only rejects path traversal (`../..`) or
+/// absolute paths smuggled in through the `restore_realmlist_backup` command.
+fn is_safe_snapshot_id(snapshot_id: &str) -> bool {
+    !snapshot_id.is_empty() && snapshot_id.chars().all(|c| c.is_ascii_digit())
+}
+
+fn backup_root(app_data_dir: &Path, host: &str) -> PathBuf {
+    app_data_dir.join(BACKUPS_DIR).join(sanitize_host(host))
+}
+
+fn snapshot_dir_for(app_data_dir: &Path, host: &str) -> PathBuf {
+    let snapshot_id = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    backup_root(app_data_dir, host).join(snapshot_id.to_string())
+}
+
+fn snapshot_if_exists(src: &Path, snapshot_dir: &Path, name: &str) -> Result<(), String> {
+    if !src.exists() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(snapshot_dir).map_err(|e| e.to_string())?;
+    let content = std::fs::read_to_string(src).map_err(|e| e.to_string())?;
+    std::fs::write(snapshot_dir.join(name), content).map_err(|e| e.to_string())
+}
+
+/// Reads whichever realmlist-bearing file already exists under `base` and
+/// returns the host it currently points at, so backups can be keyed by the
+/// realm actually being overwritten rather than the one being switched to.
+fn existing_realmlist_host(base: &Path) -> Option<String> {
+    if let Ok(content) = std::fs::read_to_string(base.join("realmlist.wtf")) {
+        if let Some(host) = extract_realmlist_host(&content) {
+            return Some(host);
+        }
+    }
+    if let Ok(content) = std::fs::read_to_string(base.join("WTF").join("Config.wtf")) {
+        if let Some(host) = extract_realmlist_host(&content) {
+            return Some(host);
+        }
+    }
+    None
+}
+
+/// Extracts the value of a `set realmlist <host>`/`SET PORTAL <host>` line, if present.
+fn extract_realmlist_host(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let mut parts = line.trim().splitn(3, ' ');
+        let set_kw = parts.next()?;
+        let key_kw = parts.next()?;
+        let value = parts.next()?;
+        if set_kw.eq_ignore_ascii_case("set")
+            && (key_kw.eq_ignore_ascii_case("realmlist") || key_kw.eq_ignore_ascii_case("portal"))
+        {
+            Some(value.trim().to_string())
+        } else {
+            None
+        }
+    })
+}
+
+fn sanitize_host(host: &str) -> String {
+    host.trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect()
+}
+
+/// Writes `content` to `path` via a temp file in the same directory followed
+/// by a rename, so a crash mid-write leaves the original file intact.
+fn atomic_write(path: &Path, content: &str) -> Result<(), String> {
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("file");
+    let tmp_path = parent.join(format!("{}.tmp", file_name));
+    std::fs::write(&tmp_path, content).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp_path, path).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static TEST_DIR_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    fn unique_temp_dir(label: &str) -> PathBuf {
+        let id = TEST_DIR_COUNTER.fetch_add(1, Ordering::SeqCst);
+        let dir = std::env::temp_dir().join(format!(
+            "realmlister_test_{}_{}_{}",
+            std::process::id(),
+            label,
+            id
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn sanitize_host_keeps_safe_characters_and_replaces_the_rest() {
+        assert_eq!(sanitize_host("logon.example.com"), "logon.example.com");
+        assert_eq!(sanitize_host("realm-1.test.net"), "realm-1.test.net");
+        assert_eq!(sanitize_host("realm:3724/?x"), "realm_3724__x");
+    }
+
+    #[test]
+    fn atomic_write_writes_content_and_leaves_no_tmp_file() {
+        let dir = unique_temp_dir("atomic_write");
+        let path = dir.join("realmlist.wtf");
+
+        atomic_write(&path, "set realmlist logon.example.com").unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "set realmlist logon.example.com"
+        );
+        assert!(!dir.join("realmlist.wtf.tmp").exists());
+    }
+
+    #[test]
+    fn write_realmlist_backs_up_and_restores_previous_content() {
+        let app_data_dir = unique_temp_dir("app_data");
+        let wow_path = unique_temp_dir("wow_path");
+        let root_realmlist = wow_path.join("realmlist.wtf");
+
+        write_realmlist(&app_data_dir, wow_path.to_str().unwrap(), "logon.example.com", "enUS", None)
+            .unwrap();
+        let original_content = std::fs::read_to_string(&root_realmlist).unwrap();
+        assert_eq!(original_content, "set realmlist logon.example.com");
+
+        // Re-pointing the realm takes a backup keyed to the *original* host
+        // (the one actually being overwritten), not the new one.
+        write_realmlist(&app_data_dir, wow_path.to_str().unwrap(), "logon2.example.com", "enUS", None)
+            .unwrap();
+        let updated_content = std::fs::read_to_string(&root_realmlist).unwrap();
+        assert_ne!(updated_content, original_content);
+
+        let backups = list_backups(&app_data_dir, "logon.example.com").unwrap();
+        assert_eq!(backups.len(), 1);
+        assert!(list_backups(&app_data_dir, "logon2.example.com").unwrap().is_empty());
+
+        restore_backup(
+            &app_data_dir,
+            wow_path.to_str().unwrap(),
+            "logon.example.com",
+            "enUS",
+            &backups[0].snapshot_id,
+        )
+        .unwrap();
+        assert_eq!(std::fs::read_to_string(&root_realmlist).unwrap(), original_content);
+    }
+
+    #[test]
+    fn write_realmlist_keeps_each_server_backup_under_its_own_host() {
+        // Server A, then server B launched from the same wow_path.
+        let app_data_dir = unique_temp_dir("app_data_multi");
+        let wow_path = unique_temp_dir("wow_path_multi");
+
+        write_realmlist(&app_data_dir, wow_path.to_str().unwrap(), "a.example.com", "enUS", None).unwrap();
+        write_realmlist(&app_data_dir, wow_path.to_str().unwrap(), "b.example.com", "enUS", None).unwrap();
+
+        let a_backups = list_backups(&app_data_dir, "a.example.com").unwrap();
+        assert_eq!(a_backups.len(), 1, "server A's original config should be backed up under its own host");
+
+        let snapshot_dir = backup_root(&app_data_dir, "a.example.com").join(&a_backups[0].snapshot_id);
+        let backed_up_content =
+            std::fs::read_to_string(snapshot_dir.join(ROOT_REALMLIST_SNAPSHOT_NAME)).unwrap();
+        assert_eq!(backed_up_content, "set realmlist a.example.com");
+    }
+
+    #[test]
+    fn restore_backup_rejects_unknown_snapshot_id() {
+        let app_data_dir = unique_temp_dir("app_data_missing");
+        let wow_path = unique_temp_dir("wow_path_missing");
+
+        let result = restore_backup(
+            &app_data_dir,
+            wow_path.to_str().unwrap(),
+            "logon.example.com",
+            "enUS",
+            "1234",
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn restore_backup_rejects_path_traversal_snapshot_id() {
+        let app_data_dir = unique_temp_dir("app_data_traversal");
+        let wow_path = unique_temp_dir("wow_path_traversal");
+
+        let result = restore_backup(
+            &app_data_dir,
+            wow_path.to_str().unwrap(),
+            "logon.example.com",
+            "enUS",
+            "../../etc",
+        );
+        assert!(result.is_err());
+    }
 }
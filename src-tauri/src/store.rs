@@ -1,6 +1,13 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+/// Current on-disk schema version for `ServerList`. Bump this and append a
+/// migration to `SERVER_LIST_MIGRATIONS` whenever the shape of `servers.json` changes.
+pub const SERVERS_SCHEMA_VERSION: u32 = 1;
+/// Current on-disk schema version for `AppSettings`. Bump this and append a
+/// migration to `SETTINGS_MIGRATIONS` whenever the shape of `settings.json` changes.
+pub const SETTINGS_SCHEMA_VERSION: u32 = 1;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Server {
     pub id: String,
@@ -15,6 +22,18 @@ pub struct Server {
     pub wow_exe: String,
     #[serde(default)]
     pub account_name: Option<String>,
+    /// Extra arguments passed to the client on launch, e.g. `-console`, `-windowed`, `-config <path>`.
+    #[serde(default)]
+    pub launch_args: Vec<String>,
+    /// Extra environment variables set on the client process.
+    #[serde(default)]
+    pub env: Vec<(String, String)>,
+    /// Shell command run (and awaited) before the client is launched.
+    #[serde(default)]
+    pub pre_launch: Option<String>,
+    /// Shell command run (and awaited) right after the client is launched.
+    #[serde(default)]
+    pub post_launch: Option<String>,
 }
 
 fn default_port() -> u16 {
@@ -25,25 +44,61 @@ fn default_wow_exe() -> String {
     "Wow.exe".to_string()
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
     #[serde(default)]
     pub default_wow_path: Option<String>,
     #[serde(default = "default_locale")]
     pub realmlist_locale: String,
+    /// Index URL for the online server directory browser.
+    #[serde(default = "crate::directory::default_directory_index_url")]
+    pub directory_index_url: String,
+    /// How often the background status monitor polls each server, in seconds.
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    #[serde(default)]
+    pub schema_version: u32,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        AppSettings {
+            default_wow_path: None,
+            realmlist_locale: default_locale(),
+            directory_index_url: crate::directory::default_directory_index_url(),
+            poll_interval_secs: default_poll_interval_secs(),
+            schema_version: SETTINGS_SCHEMA_VERSION,
+        }
+    }
 }
 
 fn default_locale() -> String {
     "enUS".to_string()
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+fn default_poll_interval_secs() -> u64 {
+    30
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ServerList {
     pub servers: Vec<Server>,
+    #[serde(default)]
+    pub schema_version: u32,
+}
+
+impl Default for ServerList {
+    fn default() -> Self {
+        ServerList {
+            servers: Vec::new(),
+            schema_version: SERVERS_SCHEMA_VERSION,
+        }
+    }
 }
 
 const SERVERS_FILE: &str = "servers.json";
 const SETTINGS_FILE: &str = "settings.json";
+const BACKUP_SUFFIX: &str = "bak";
 
 pub fn servers_path(app_data_dir: &std::path::Path) -> PathBuf {
     app_data_dir.join(SERVERS_FILE)
@@ -53,13 +108,68 @@ pub fn settings_path(app_data_dir: &std::path::Path) -> PathBuf {
     app_data_dir.join(SETTINGS_FILE)
 }
 
+/// A single schema upgrade step, transforming the raw JSON from one version to the next.
+type Migration = fn(serde_json::Value) -> serde_json::Value;
+
+/// `SERVER_LIST_MIGRATIONS[n]` upgrades a `servers.json` document from
+/// schema version `n` to `n + 1`. Applied in order starting from the
+/// document's current `schema_version` (treated as 0 if absent).
+const SERVER_LIST_MIGRATIONS: &[Migration] = &[migrate_servers_v0_to_v1];
+
+/// `SETTINGS_MIGRATIONS[n]` upgrades a `settings.json` document from
+/// schema version `n` to `n + 1`, mirroring `SERVER_LIST_MIGRATIONS`.
+const SETTINGS_MIGRATIONS: &[Migration] = &[migrate_settings_v0_to_v1];
+
+fn migrate_servers_v0_to_v1(mut value: serde_json::Value) -> serde_json::Value {
+    if let Some(obj) = value.as_object_mut() {
+        obj.entry("servers").or_insert_with(|| serde_json::json!([]));
+        obj.insert("schema_version".to_string(), serde_json::json!(1));
+    }
+    value
+}
+
+fn migrate_settings_v0_to_v1(mut value: serde_json::Value) -> serde_json::Value {
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("schema_version".to_string(), serde_json::json!(1));
+    }
+    value
+}
+
+/// Applies every migration needed to bring `value` up to `migrations.len()`,
+/// starting from its current `schema_version` field (0 if missing). Returns
+/// the migrated JSON and whether any migration actually ran.
+fn migrate(mut value: serde_json::Value, migrations: &[Migration]) -> (serde_json::Value, bool) {
+    let mut version = value
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as usize;
+    let started_at = version;
+    while version < migrations.len() {
+        value = migrations[version](value);
+        version += 1;
+    }
+    (value, version != started_at)
+}
+
+fn backup_file(path: &std::path::Path, original_contents: &str) -> Result<(), String> {
+    let backup_path = path.with_extension(BACKUP_SUFFIX);
+    std::fs::write(backup_path, original_contents).map_err(|e| e.to_string())
+}
+
 pub fn load_servers(app_data_dir: &std::path::Path) -> Result<ServerList, String> {
     let path = servers_path(app_data_dir);
     if !path.exists() {
         return Ok(ServerList::default());
     }
-    let data = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    serde_json::from_str(&data).map_err(|e| e.to_string())
+    let raw = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+    let (migrated, was_migrated) = migrate(value, SERVER_LIST_MIGRATIONS);
+    let list: ServerList = serde_json::from_value(migrated).map_err(|e| e.to_string())?;
+    if was_migrated {
+        backup_file(&path, &raw)?;
+        save_servers(app_data_dir, &list)?;
+    }
+    Ok(list)
 }
 
 pub fn save_servers(app_data_dir: &std::path::Path, list: &ServerList) -> Result<(), String> {
@@ -74,8 +184,15 @@ pub fn load_settings(app_data_dir: &std::path::Path) -> Result<AppSettings, Stri
     if !path.exists() {
         return Ok(AppSettings::default());
     }
-    let data = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    serde_json::from_str(&data).map_err(|e| e.to_string())
+    let raw = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+    let (migrated, was_migrated) = migrate(value, SETTINGS_MIGRATIONS);
+    let settings: AppSettings = serde_json::from_value(migrated).map_err(|e| e.to_string())?;
+    if was_migrated {
+        backup_file(&path, &raw)?;
+        save_settings(app_data_dir, &settings)?;
+    }
+    Ok(settings)
 }
 
 pub fn save_settings(
@@ -87,3 +204,103 @@ pub fn save_settings(
     let data = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
     std::fs::write(path, data).map_err(|e| e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static TEST_DIR_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    fn unique_temp_dir(label: &str) -> PathBuf {
+        let id = TEST_DIR_COUNTER.fetch_add(1, Ordering::SeqCst);
+        let dir = std::env::temp_dir().join(format!("store_test_{}_{}_{}", std::process::id(), label, id));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn load_servers_migrates_legacy_file_and_writes_bak() {
+        let dir = unique_temp_dir("load_servers");
+        let legacy_raw = r#"{"servers":[{"id":"1","name":"Test Realm","realmlist_host":"logon.example.com","port":3724,"wow_exe":"Wow.exe"}]}"#;
+        std::fs::write(servers_path(&dir), legacy_raw).unwrap();
+
+        let list = load_servers(&dir).unwrap();
+        assert_eq!(list.schema_version, SERVERS_SCHEMA_VERSION);
+        assert_eq!(list.servers.len(), 1);
+
+        let backup_path = servers_path(&dir).with_extension(BACKUP_SUFFIX);
+        assert!(backup_path.exists(), "expected a .bak file preserving the pre-migration servers.json");
+        assert_eq!(std::fs::read_to_string(&backup_path).unwrap(), legacy_raw);
+
+        let on_disk: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(servers_path(&dir)).unwrap()).unwrap();
+        assert_eq!(on_disk["schema_version"], json!(SERVERS_SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn load_servers_does_not_write_bak_for_current_file() {
+        let dir = unique_temp_dir("load_servers_current");
+        let current_raw = format!(r#"{{"servers":[],"schema_version":{}}}"#, SERVERS_SCHEMA_VERSION);
+        std::fs::write(servers_path(&dir), &current_raw).unwrap();
+
+        load_servers(&dir).unwrap();
+
+        assert!(!servers_path(&dir).with_extension(BACKUP_SUFFIX).exists());
+    }
+
+    #[test]
+    fn load_settings_migrates_legacy_file_and_writes_bak() {
+        let dir = unique_temp_dir("load_settings");
+        let legacy_raw = r#"{"realmlist_locale":"enUS"}"#;
+        std::fs::write(settings_path(&dir), legacy_raw).unwrap();
+
+        let settings = load_settings(&dir).unwrap();
+        assert_eq!(settings.schema_version, SETTINGS_SCHEMA_VERSION);
+
+        let backup_path = settings_path(&dir).with_extension(BACKUP_SUFFIX);
+        assert!(backup_path.exists(), "expected a .bak file preserving the pre-migration settings.json");
+        assert_eq!(std::fs::read_to_string(&backup_path).unwrap(), legacy_raw);
+
+        let on_disk: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(settings_path(&dir)).unwrap()).unwrap();
+        assert_eq!(on_disk["schema_version"], json!(SETTINGS_SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn migrates_servers_missing_version_to_current() {
+        let legacy = json!({
+            "servers": [{
+                "id": "1",
+                "name": "Test Realm",
+                "realmlist_host": "logon.example.com",
+                "port": 3724,
+                "wow_exe": "Wow.exe"
+            }]
+        });
+        let (migrated, was_migrated) = migrate(legacy, SERVER_LIST_MIGRATIONS);
+        assert!(was_migrated);
+        assert_eq!(migrated["schema_version"], json!(SERVERS_SCHEMA_VERSION));
+        let list: ServerList = serde_json::from_value(migrated).unwrap();
+        assert_eq!(list.schema_version, SERVERS_SCHEMA_VERSION);
+        assert_eq!(list.servers.len(), 1);
+    }
+
+    #[test]
+    fn does_not_remigrate_current_servers() {
+        let current = json!({ "servers": [], "schema_version": SERVERS_SCHEMA_VERSION });
+        let (_, was_migrated) = migrate(current, SERVER_LIST_MIGRATIONS);
+        assert!(!was_migrated);
+    }
+
+    #[test]
+    fn migrates_settings_missing_version_to_current() {
+        let legacy = json!({ "realmlist_locale": "enUS" });
+        let (migrated, was_migrated) = migrate(legacy, SETTINGS_MIGRATIONS);
+        assert!(was_migrated);
+        assert_eq!(migrated["schema_version"], json!(SETTINGS_SCHEMA_VERSION));
+        let settings: AppSettings = serde_json::from_value(migrated).unwrap();
+        assert_eq!(settings.schema_version, SETTINGS_SCHEMA_VERSION);
+    }
+}